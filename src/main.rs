@@ -3,16 +3,23 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use roux::comment::CommentData;
 use roux::Subreddit;
+use serde::{Deserialize, Serialize};
 use std::{
     error::Error,
+    fs,
     io,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
     time::{Duration, Instant},
 };
 use tui::{
     backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
 
@@ -30,6 +37,9 @@ impl<T> StatefulList<T> {
     }
 
     fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -44,6 +54,9 @@ impl<T> StatefulList<T> {
     }
 
     fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -62,58 +75,744 @@ impl<T> StatefulList<T> {
     }
 }
 
+#[derive(Clone, Copy)]
 enum SubSort {
     Hot,
     Rising,
     Popular,
 }
 
+impl SubSort {
+    fn next(self) -> SubSort {
+        match self {
+            SubSort::Hot => SubSort::Rising,
+            SubSort::Rising => SubSort::Popular,
+            SubSort::Popular => SubSort::Hot,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SubSort::Hot => "hot",
+            SubSort::Rising => "rising",
+            SubSort::Popular => "top",
+        }
+    }
+}
+
 enum ViewState {
     Subreddit,
     Post,
 }
 
+// Which pane of the split subreddit view currently has focus, so its border
+// can be highlighted and Tab knows what to switch away from.
+#[derive(Clone, Copy, PartialEq)]
+enum Focus {
+    List,
+    Preview,
+}
+
+impl Focus {
+    fn toggle(self) -> Focus {
+        match self {
+            Focus::List => Focus::Preview,
+            Focus::Preview => Focus::List,
+        }
+    }
+}
+
+enum InputMode {
+    Normal,
+    Editing,
+    LoginUsername,
+    LoginPassword,
+}
+
+const SESSION_PATH: &str = "session.json";
+
+// The authenticated state persisted to disk between runs. We keep the OAuth
+// refresh token rather than the password, following the same restore-a-Session
+// pattern a Matrix client's account manager uses.
+#[derive(Clone, Serialize, Deserialize)]
+struct Session {
+    username: String,
+    refresh_token: String,
+}
+
+impl Session {
+    fn load(path: &str) -> Option<Session> {
+        let data = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    fn save(&self, path: &str) {
+        if let Ok(data) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, data);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum VoteDirection {
+    Up,
+    Down,
+    None,
+}
+
+// A comment flattened out of the reply tree roux hands back, annotated with
+// its depth so it can be indented and its subtree can be collapsed.
+#[derive(Clone)]
+struct Comment {
+    author: String,
+    body: String,
+    score: i64,
+    depth: usize,
+    collapsed: bool,
+    // Position in the (unfiltered) `comments_all` this was flattened into,
+    // so a visible, possibly-filtered copy can still be toggled unambiguously
+    // instead of matching back by depth/author.
+    original_index: usize,
+}
+
+// Walks roux's nested `replies` structure and appends each comment to `out`
+// in display order, depth-first, recording how deep each one is nested.
+fn flatten_comments(children: &[roux::comment::Child<CommentData>], depth: usize, out: &mut Vec<Comment>) {
+    for child in children {
+        let data = &child.data;
+        let original_index = out.len();
+        out.push(Comment {
+            author: data.author.clone().unwrap_or_else(|| "[deleted]".to_string()),
+            body: data.body.clone().unwrap_or_default(),
+            score: data.score.unwrap_or(0),
+            depth,
+            collapsed: false,
+            original_index,
+        });
+        if let roux::comment::Replies::Comments(root) = &data.replies {
+            flatten_comments(&root.data.children, depth + 1, out);
+        }
+    }
+}
+
+// Converts Reddit-flavoured markdown into styled `Text` for the post body:
+// `#` headers go bold, `> ` quotes are dimmed, fenced/inline code gets its
+// own style, and `[text](url)` links are underlined.
+fn markdown_to_text(src: &str) -> Text<'static> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    for line in src.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Spans::from(Span::styled(
+                line.to_string(),
+                Style::default().fg(Color::Yellow),
+            )));
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('#') {
+            lines.push(Spans::from(Span::styled(
+                header.trim_start_matches('#').trim().to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            continue;
+        }
+        if let Some(quote) = line.trim_start().strip_prefix("> ") {
+            lines.push(Spans::from(Span::styled(
+                quote.to_string(),
+                Style::default().fg(Color::DarkGray),
+            )));
+            continue;
+        }
+        lines.push(Spans::from(markdown_inline_spans(line)));
+    }
+    Text::from(lines)
+}
+
+// Splits a single line into spans, styling inline `code` and `[text](url)`
+// links while leaving everything else as plain text.
+fn markdown_inline_spans(line: &str) -> Vec<Span<'static>> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Vec::new();
+    let mut buf = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            if let Some(end) = chars[i + 1..].iter().position(|&c| c == '`') {
+                if !buf.is_empty() {
+                    spans.push(Span::raw(std::mem::take(&mut buf)));
+                }
+                let code: String = chars[i + 1..i + 1 + end].iter().collect();
+                spans.push(Span::styled(code, Style::default().fg(Color::Yellow)));
+                i += end + 2;
+                continue;
+            }
+        }
+        if chars[i] == '[' {
+            if let Some(bracket_end) = chars[i..].iter().position(|&c| c == ']') {
+                let bracket_end = i + bracket_end;
+                if chars.get(bracket_end + 1) == Some(&'(') {
+                    if let Some(paren_end) = chars[bracket_end + 2..].iter().position(|&c| c == ')') {
+                        let paren_end = bracket_end + 2 + paren_end;
+                        if !buf.is_empty() {
+                            spans.push(Span::raw(std::mem::take(&mut buf)));
+                        }
+                        let link_text: String = chars[i + 1..bracket_end].iter().collect();
+                        spans.push(Span::styled(
+                            link_text,
+                            Style::default().add_modifier(Modifier::UNDERLINED),
+                        ));
+                        i = paren_end + 1;
+                        continue;
+                    }
+                }
+            }
+        }
+        buf.push(chars[i]);
+        i += 1;
+    }
+    if !buf.is_empty() {
+        spans.push(Span::raw(buf));
+    }
+    spans
+}
+
+// Renders a comment's indentation as tree glyphs, e.g. "│  ├─ " for a
+// third-level reply.
+fn comment_indent(depth: usize) -> String {
+    if depth == 0 {
+        String::new()
+    } else {
+        let mut indent = "│  ".repeat(depth - 1);
+        indent.push_str("├─ ");
+        indent
+    }
+}
+
+// A request for the background fetch worker to act on; it owns the `roux`
+// client and never runs on the render thread.
+enum FetchRequest {
+    Submissions { subreddit: String, sort: SubSort },
+    Comments { subreddit: String, id: String },
+    Login { username: String, password: String },
+    // Restores a client from a persisted `Session` instead of a password, so
+    // a saved session can actually authenticate the worker on startup.
+    RestoreSession(Session),
+    Vote {
+        id: String,
+        direction: VoteDirection,
+        previous: Option<bool>,
+    },
+    SaveToggle {
+        id: String,
+        save: bool,
+        previous: bool,
+    },
+}
+
+// What the worker hands back once a `FetchRequest` completes. Errors are
+// flattened to `String` since `RouxError` doesn't need to cross the channel
+// as anything richer than a status message.
+enum FetchResult {
+    Submissions(Result<Vec<Submission>, String>),
+    Comments(Result<Vec<Comment>, String>),
+    LoggedIn(Result<Session, String>),
+    // Carries the pre-vote `liked` state alongside the outcome so a failed
+    // vote can revert the optimistic update instead of leaving a stale score.
+    Voted {
+        id: String,
+        previous: Option<bool>,
+        outcome: Result<VoteDirection, String>,
+    },
+    // Same idea as `Voted`, but for the save marker.
+    Saved {
+        id: String,
+        previous: bool,
+        outcome: Result<bool, String>,
+    },
+}
+
+// Spawns the thread that owns the `roux` client, and returns the channel
+// pair the UI uses to drive it: send `FetchRequest`s in, drain
+// `FetchResult`s out on each tick.
+fn spawn_fetch_worker() -> (Sender<FetchRequest>, Receiver<FetchResult>) {
+    let (request_tx, request_rx) = mpsc::channel::<FetchRequest>();
+    let (result_tx, result_rx) = mpsc::channel::<FetchResult>();
+
+    thread::spawn(move || {
+        let mut me: Option<roux::Me> = None;
+        for request in request_rx {
+            let result = match request {
+                FetchRequest::Submissions { subreddit, sort } => {
+                    let sub = Subreddit::new(&subreddit);
+                    let listing = match sort {
+                        SubSort::Hot => sub.hot(25, None),
+                        SubSort::Rising => sub.rising(25, None),
+                        SubSort::Popular => sub.top(25, None),
+                    };
+                    FetchResult::Submissions(
+                        listing
+                            .map(|listing| {
+                                listing
+                                    .data
+                                    .children
+                                    .iter()
+                                    .map(|c| Submission {
+                                        title: c.data.title.clone(),
+                                        score: c.data.score,
+                                        id: c.data.id.clone(),
+                                        selftext: c.data.selftext.clone(),
+                                        liked: c.data.likes,
+                                        saved: c.data.saved,
+                                    })
+                                    .collect()
+                            })
+                            .map_err(|e| format!("{:?}", e)),
+                    )
+                }
+                FetchRequest::Comments { subreddit, id } => {
+                    let sub = Subreddit::new(&subreddit);
+                    FetchResult::Comments(
+                        sub.article_comments(&id, Some(8), Some(100))
+                            .map(|root| {
+                                let mut out = Vec::new();
+                                flatten_comments(&root.data.children, 0, &mut out);
+                                out
+                            })
+                            .map_err(|e| format!("{:?}", e)),
+                    )
+                }
+                FetchRequest::Login { username, password } => {
+                    let client_id = std::env::var("REDDIT_CLIENT_ID").unwrap_or_default();
+                    let client_secret = std::env::var("REDDIT_CLIENT_SECRET").unwrap_or_default();
+                    let login = roux::Reddit::new("reddit-tui", &client_id, &client_secret)
+                        .username(&username)
+                        .password(&password)
+                        .login();
+                    FetchResult::LoggedIn(match login {
+                        Ok(client) => {
+                            let session = Session {
+                                username: username.clone(),
+                                refresh_token: client.refresh_token.clone(),
+                            };
+                            me = Some(client);
+                            Ok(session)
+                        }
+                        Err(e) => Err(format!("{:?}", e)),
+                    })
+                }
+                FetchRequest::RestoreSession(session) => {
+                    let client_id = std::env::var("REDDIT_CLIENT_ID").unwrap_or_default();
+                    let client_secret = std::env::var("REDDIT_CLIENT_SECRET").unwrap_or_default();
+                    let login = roux::Reddit::new("reddit-tui", &client_id, &client_secret)
+                        .refresh_token(&session.refresh_token)
+                        .login();
+                    FetchResult::LoggedIn(match login {
+                        Ok(client) => {
+                            let session = Session {
+                                username: session.username,
+                                refresh_token: client.refresh_token.clone(),
+                            };
+                            me = Some(client);
+                            Ok(session)
+                        }
+                        Err(e) => Err(format!("{:?}", e)),
+                    })
+                }
+                FetchRequest::Vote {
+                    id,
+                    direction,
+                    previous,
+                } => {
+                    let outcome = match &me {
+                        Some(client) => match direction {
+                            VoteDirection::Up => client.upvote(&id),
+                            VoteDirection::Down => client.downvote(&id),
+                            VoteDirection::None => client.unvote(&id),
+                        }
+                        .map_err(|e| format!("{:?}", e)),
+                        None => Err("log in with L to vote".to_string()),
+                    };
+                    FetchResult::Voted {
+                        id,
+                        previous,
+                        outcome: outcome.map(|_| direction),
+                    }
+                }
+                FetchRequest::SaveToggle { id, save, previous } => {
+                    let outcome = match &me {
+                        Some(client) => {
+                            if save {
+                                client.save(&id)
+                            } else {
+                                client.unsave(&id)
+                            }
+                        }
+                        .map_err(|e| format!("{:?}", e)),
+                        None => Err("log in with L to save".to_string()),
+                    };
+                    FetchResult::Saved {
+                        id,
+                        previous,
+                        outcome: outcome.map(|_| save),
+                    }
+                }
+            };
+            if result_tx.send(result).is_err() {
+                return;
+            }
+        }
+    });
+
+    (request_tx, result_rx)
+}
+
 struct App {
     submissions: StatefulList<Submission>,
+    comments_all: Vec<Comment>,
+    comments: StatefulList<Comment>,
     view_state: ViewState,
+    input_mode: InputMode,
+    input: String,
     selection: Option<Submission>,
-    subreddit: Subreddit,
-    // comments: roux::Comments,
+    subreddit_name: String,
+    sort: SubSort,
+    request_tx: Sender<FetchRequest>,
+    result_rx: Receiver<FetchResult>,
+    // Count of requests sent but not yet answered, rather than a single bool,
+    // so an unrelated reply (e.g. a vote landing while comments are still
+    // loading) doesn't clear the "loading..." indicator out from under a
+    // fetch that's genuinely still in flight.
+    pending_requests: u32,
+    status: Option<String>,
+    session: Option<Session>,
+    pending_username: String,
+    post_scroll: u16,
+    focus: Focus,
+    // Scroll offset for the preview pane, independent of the list's own
+    // selection so `Focus::Preview` actually does something (scroll the
+    // selftext) instead of just recoloring a border.
+    preview_scroll: u16,
+    // Set when a submissions fetch is owed (initially, and by an `r` retry;
+    // subreddit/sort changes send their fetch directly) and cleared the
+    // moment one is sent, so the tick loop doesn't
+    // keep re-requesting just because the list happens to be empty (a
+    // legitimately empty subreddit, or the last fetch having errored).
+    needs_submissions: bool,
 }
 
 impl App {
-    fn new() -> App {
-        App {
+    fn new(session: Option<Session>) -> App {
+        let (request_tx, result_rx) = spawn_fetch_worker();
+        let mut app = App {
             submissions: StatefulList::with_items(Vec::new()),
+            comments_all: Vec::new(),
+            comments: StatefulList::with_items(Vec::new()),
             view_state: ViewState::Subreddit,
+            input_mode: InputMode::Normal,
+            input: String::new(),
             selection: None,
-            subreddit: Subreddit::new("rust"),
-            // comments: roux::Comments::from(""),
+            subreddit_name: "rust".to_string(),
+            sort: SubSort::Popular,
+            request_tx,
+            result_rx,
+            pending_requests: 0,
+            // Not set from the restored session directly: the worker hasn't
+            // authenticated yet, so voting/saving would fail until the
+            // `RestoreSession` round trip below confirms it actually logged in.
+            session: None,
+            pending_username: String::new(),
+            post_scroll: 0,
+            focus: Focus::List,
+            preview_scroll: 0,
+            needs_submissions: true,
+        };
+        app.request_submissions();
+        if let Some(session) = session {
+            app.pending_requests += 1;
+            let _ = app.request_tx.send(FetchRequest::RestoreSession(session));
+        }
+        app
+    }
+
+    // Drains whatever the fetch worker has finished since the last tick.
+    fn on_tick(&mut self) {
+        while let Ok(result) = self.result_rx.try_recv() {
+            self.pending_requests = self.pending_requests.saturating_sub(1);
+            match result {
+                FetchResult::Submissions(Ok(submissions)) => {
+                    self.submissions = StatefulList::with_items(submissions);
+                    if !self.submissions.items.is_empty() {
+                        self.submissions.state.select(Some(0));
+                    }
+                    self.status = None;
+                }
+                FetchResult::Submissions(Err(e)) => self.status = Some(e),
+                FetchResult::Comments(Ok(comments)) => {
+                    self.comments_all = comments;
+                    self.rebuild_visible_comments();
+                    self.status = None;
+                }
+                FetchResult::Comments(Err(e)) => self.status = Some(e),
+                FetchResult::LoggedIn(Ok(session)) => {
+                    session.save(SESSION_PATH);
+                    self.status = Some(format!("logged in as {}", session.username));
+                    self.session = Some(session);
+                }
+                FetchResult::LoggedIn(Err(e)) => self.status = Some(e),
+                FetchResult::Voted {
+                    id,
+                    previous: _,
+                    outcome: Ok(direction),
+                } => {
+                    let liked = match direction {
+                        VoteDirection::Up => Some(true),
+                        VoteDirection::Down => Some(false),
+                        VoteDirection::None => None,
+                    };
+                    self.apply_vote(&id, liked);
+                    self.status = None;
+                }
+                FetchResult::Voted {
+                    id,
+                    previous,
+                    outcome: Err(e),
+                } => {
+                    // The vote never landed server-side, so undo the optimistic
+                    // update rather than leaving a score that doesn't match reality.
+                    self.apply_vote(&id, previous);
+                    self.status = Some(e);
+                }
+                FetchResult::Saved {
+                    id,
+                    previous: _,
+                    outcome: Ok(saved),
+                } => {
+                    self.apply_saved(&id, saved);
+                    self.status = None;
+                }
+                FetchResult::Saved {
+                    id,
+                    previous,
+                    outcome: Err(e),
+                } => {
+                    self.apply_saved(&id, previous);
+                    self.status = Some(e);
+                }
+            }
         }
     }
 
-    fn on_tick(&mut self) {}
+    fn apply_vote(&mut self, id: &str, liked: Option<bool>) {
+        if let Some(submission) = self.submissions.items.iter_mut().find(|s| s.id == id) {
+            submission.score += vote_delta(submission.liked, liked);
+            submission.liked = liked;
+        }
+        if let Some(selection) = self.selection.as_mut().filter(|s| s.id == id) {
+            selection.score += vote_delta(selection.liked, liked);
+            selection.liked = liked;
+        }
+    }
+
+    fn apply_saved(&mut self, id: &str, saved: bool) {
+        if let Some(submission) = self.submissions.items.iter_mut().find(|s| s.id == id) {
+            submission.saved = saved;
+        }
+        if let Some(selection) = self.selection.as_mut().filter(|s| s.id == id) {
+            selection.saved = saved;
+        }
+    }
+
+    fn loading(&self) -> bool {
+        self.pending_requests > 0
+    }
+
+    // Returns the submission that vote/save key bindings should act on:
+    // the highlighted row in the list view, or the open post in the post view.
+    fn active_submission(&self) -> Option<&Submission> {
+        match self.view_state {
+            ViewState::Subreddit => self
+                .submissions
+                .state
+                .selected()
+                .and_then(|i| self.submissions.items.get(i)),
+            ViewState::Post => self.selection.as_ref(),
+        }
+    }
+
+    fn vote(&mut self, direction: VoteDirection) {
+        if self.session.is_none() {
+            self.status = Some("log in with L to vote".to_string());
+            return;
+        }
+        if let Some(submission) = self.active_submission() {
+            let new_liked = match direction {
+                VoteDirection::Up if submission.liked == Some(true) => None,
+                VoteDirection::Down if submission.liked == Some(false) => None,
+                VoteDirection::Up => Some(true),
+                VoteDirection::Down => Some(false),
+                VoteDirection::None => None,
+            };
+            let direction = match new_liked {
+                Some(true) => VoteDirection::Up,
+                Some(false) => VoteDirection::Down,
+                None => VoteDirection::None,
+            };
+            let id = submission.id.clone();
+            let previous = submission.liked;
+            self.apply_vote(&id, new_liked);
+            self.pending_requests += 1;
+            let _ = self.request_tx.send(FetchRequest::Vote {
+                id,
+                direction,
+                previous,
+            });
+        }
+    }
+
+    fn toggle_save(&mut self) {
+        if self.session.is_none() {
+            self.status = Some("log in with L to save".to_string());
+            return;
+        }
+        if let Some(submission) = self.active_submission() {
+            let save = !submission.saved;
+            let id = submission.id.clone();
+            let previous = submission.saved;
+            self.apply_saved(&id, save);
+            self.pending_requests += 1;
+            let _ = self.request_tx.send(FetchRequest::SaveToggle {
+                id,
+                save,
+                previous,
+            });
+        }
+    }
+
+    fn start_login(&mut self) {
+        self.pending_username.clear();
+        self.input.clear();
+        self.input_mode = InputMode::LoginUsername;
+    }
+
+    fn submit_login_username(&mut self) {
+        self.pending_username = self.input.clone();
+        self.input.clear();
+        self.input_mode = InputMode::LoginPassword;
+    }
+
+    fn submit_login_password(&mut self) {
+        self.pending_requests += 1;
+        let _ = self.request_tx.send(FetchRequest::Login {
+            username: self.pending_username.clone(),
+            password: self.input.clone(),
+        });
+        self.input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn request_submissions(&mut self) {
+        self.pending_requests += 1;
+        self.needs_submissions = false;
+        let _ = self.request_tx.send(FetchRequest::Submissions {
+            subreddit: self.subreddit_name.clone(),
+            sort: self.sort,
+        });
+    }
+
+    // Swaps in a freshly named subreddit and asks the worker to refetch its
+    // listing.
+    fn set_subreddit(&mut self, name: &str) {
+        self.subreddit_name = name.to_string();
+        self.submissions = StatefulList::with_items(Vec::new());
+        self.request_submissions();
+    }
+
+    fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.submissions = StatefulList::with_items(Vec::new());
+        self.request_submissions();
+    }
+
+    // Lets the user explicitly retry a fetch that errored out, rather than
+    // the tick loop silently hammering Reddit on its own.
+    fn retry_submissions(&mut self) {
+        self.needs_submissions = true;
+    }
+
+    // Rebuilds the visible comment list from `comments_all`, skipping every
+    // descendant of a collapsed comment until depth drops back to its level.
+    // Keeps whichever comment was selected before the rebuild selected
+    // afterwards, so collapsing a subtree doesn't bounce the cursor to the top.
+    fn rebuild_visible_comments(&mut self) {
+        let selected_original = self
+            .comments
+            .state
+            .selected()
+            .and_then(|i| self.comments.items.get(i))
+            .map(|c| c.original_index);
+
+        let mut visible = Vec::new();
+        let mut skip_below: Option<usize> = None;
+        for comment in &self.comments_all {
+            if let Some(depth) = skip_below {
+                if comment.depth > depth {
+                    continue;
+                }
+                skip_below = None;
+            }
+            if comment.collapsed {
+                skip_below = Some(comment.depth);
+            }
+            visible.push(comment.clone());
+        }
+        self.comments = StatefulList::with_items(visible);
+        if self.comments.items.is_empty() {
+            return;
+        }
+        let new_index = selected_original
+            .and_then(|orig| {
+                self.comments
+                    .items
+                    .iter()
+                    .position(|c| c.original_index == orig)
+            })
+            .unwrap_or(0);
+        self.comments.state.select(Some(new_index));
+    }
 
     fn select(&mut self) {
         match self.view_state {
             ViewState::Subreddit => {
                 if self.submissions.items.len() > 0 {
                     if let Some(i) = self.submissions.state.selected() {
-                        self.selection = Some(self.submissions.items.get(i).unwrap().clone());
-                        let article = self.subreddit.article_comments(
-                            &self.selection.as_ref().unwrap().id,
-                            Some(8),
-                            Some(100),
-                        );
-                        // eprintln!(
-                        //     "{:?}",
-                        //     article.unwrap().data.children.first().unwrap().data.body
-                        // );
+                        let selection = self.submissions.items.get(i).unwrap().clone();
+                        self.comments_all = Vec::new();
+                        self.comments = StatefulList::with_items(Vec::new());
+                        self.pending_requests += 1;
+                        let _ = self.request_tx.send(FetchRequest::Comments {
+                            subreddit: self.subreddit_name.clone(),
+                            id: selection.id.clone(),
+                        });
+                        self.selection = Some(selection);
+                        self.post_scroll = 0;
                         self.view_state = ViewState::Post;
                     }
                 }
             }
-            ViewState::Post => todo!(),
+            ViewState::Post => {
+                if let Some(i) = self.comments.state.selected() {
+                    if let Some(selected) = self.comments.items.get(i) {
+                        if let Some(original) = self.comments_all.get_mut(selected.original_index) {
+                            original.collapsed = !original.collapsed;
+                        }
+                        self.rebuild_visible_comments();
+                    }
+                }
+            }
         }
     }
 
@@ -121,6 +820,13 @@ impl App {
         self.view_state = ViewState::Subreddit;
     }
 
+    fn scroll_post(&mut self, delta: i32) {
+        self.post_scroll = self.post_scroll.saturating_add_signed(delta as i16);
+    }
+
+    fn scroll_preview(&mut self, delta: i32) {
+        self.preview_scroll = self.preview_scroll.saturating_add_signed(delta as i16);
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -133,7 +839,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // create app and run it
     let tick_rate = Duration::from_millis(250);
-    let app = App::new();
+    let app = App::new(Session::load(SESSION_PATH));
     let res = run_app(&mut terminal, app, tick_rate);
 
     // restore terminal
@@ -167,14 +873,98 @@ fn run_app<B: Backend>(
         if crossterm::event::poll(timeout)? {
             if let Some(evt) = event::read().ok() {
                 if let Event::Key(key) = evt {
-                    match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Left => app.submissions.unselect(),
-                        KeyCode::Down => app.submissions.next(),
-                        KeyCode::Up => app.submissions.previous(),
-                        KeyCode::Enter => app.select(),
-                        KeyCode::Esc | KeyCode::Backspace => app.back(),
-                        _ => {}
+                    match app.input_mode {
+                        InputMode::Normal => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Char('/') | KeyCode::Char(':') => {
+                                app.input.clear();
+                                app.input_mode = InputMode::Editing;
+                            }
+                            KeyCode::Char('s') if matches!(app.view_state, ViewState::Subreddit) => {
+                                app.cycle_sort()
+                            }
+                            KeyCode::Char('L') if app.session.is_none() => app.start_login(),
+                            KeyCode::Char('u') => app.vote(VoteDirection::Up),
+                            KeyCode::Char('d') => app.vote(VoteDirection::Down),
+                            KeyCode::Char('S') => app.toggle_save(),
+                            KeyCode::Char('r')
+                                if matches!(app.view_state, ViewState::Subreddit)
+                                    && app.submissions.items.is_empty() =>
+                            {
+                                app.retry_submissions()
+                            }
+                            KeyCode::Tab if matches!(app.view_state, ViewState::Subreddit) => {
+                                app.focus = app.focus.toggle()
+                            }
+                            KeyCode::Char('j') if matches!(app.view_state, ViewState::Post) => {
+                                app.scroll_post(1)
+                            }
+                            KeyCode::Char('k') if matches!(app.view_state, ViewState::Post) => {
+                                app.scroll_post(-1)
+                            }
+                            KeyCode::PageDown if matches!(app.view_state, ViewState::Post) => {
+                                app.scroll_post(10)
+                            }
+                            KeyCode::PageUp if matches!(app.view_state, ViewState::Post) => {
+                                app.scroll_post(-10)
+                            }
+                            KeyCode::Left => app.submissions.unselect(),
+                            KeyCode::Down => match app.view_state {
+                                ViewState::Subreddit => match app.focus {
+                                    Focus::List => {
+                                        app.submissions.next();
+                                        app.preview_scroll = 0;
+                                    }
+                                    Focus::Preview => app.scroll_preview(1),
+                                },
+                                ViewState::Post => app.comments.next(),
+                            },
+                            KeyCode::Up => match app.view_state {
+                                ViewState::Subreddit => match app.focus {
+                                    Focus::List => {
+                                        app.submissions.previous();
+                                        app.preview_scroll = 0;
+                                    }
+                                    Focus::Preview => app.scroll_preview(-1),
+                                },
+                                ViewState::Post => app.comments.previous(),
+                            },
+                            KeyCode::Enter => app.select(),
+                            KeyCode::Esc | KeyCode::Backspace => app.back(),
+                            _ => {}
+                        },
+                        InputMode::Editing => match key.code {
+                            KeyCode::Enter => {
+                                if !app.input.is_empty() {
+                                    app.set_subreddit(&app.input.clone());
+                                }
+                                app.input_mode = InputMode::Normal;
+                            }
+                            KeyCode::Esc => app.input_mode = InputMode::Normal,
+                            KeyCode::Backspace => {
+                                app.input.pop();
+                            }
+                            KeyCode::Char(c) => app.input.push(c),
+                            _ => {}
+                        },
+                        InputMode::LoginUsername => match key.code {
+                            KeyCode::Enter => app.submit_login_username(),
+                            KeyCode::Esc => app.input_mode = InputMode::Normal,
+                            KeyCode::Backspace => {
+                                app.input.pop();
+                            }
+                            KeyCode::Char(c) => app.input.push(c),
+                            _ => {}
+                        },
+                        InputMode::LoginPassword => match key.code {
+                            KeyCode::Enter => app.submit_login_password(),
+                            KeyCode::Esc => app.input_mode = InputMode::Normal,
+                            KeyCode::Backspace => {
+                                app.input.pop();
+                            }
+                            KeyCode::Char(c) => app.input.push(c),
+                            _ => {}
+                        },
                     }
                 } else if let Event::Resize(w, h) = evt {
                     println!("resized to {w} {h}");
@@ -182,23 +972,8 @@ fn run_app<B: Backend>(
             }
         }
         if last_tick.elapsed() >= tick_rate {
-            if app.submissions.items.len() < 10 {
-                app.submissions = StatefulList::with_items(
-                    app.subreddit
-                        .top(25, None)
-                        .unwrap()
-                        .data
-                        .children
-                        .iter_mut()
-                        .map(|c| Submission {
-                            title: c.data.title.clone(),
-                            score: c.data.score,
-                            id: c.data.id.clone(),
-                            selftext: c.data.selftext.clone(),
-                        })
-                        .collect(),
-                );
-                app.submissions.state.select(Some(0));
+            if app.needs_submissions && !app.loading() {
+                app.request_submissions();
             }
             app.on_tick();
             last_tick = Instant::now();
@@ -212,36 +987,173 @@ struct Submission {
     score: f64,
     id: String,
     selftext: String,
+    // Mirrors Reddit's own `likes` field: `Some(true)` upvoted, `Some(false)`
+    // downvoted, `None` no vote cast.
+    liked: Option<bool>,
+    saved: bool,
+}
+
+// How much a submission's displayed score should move when its vote state
+// changes from `old` to `new`, so the UI can update optimistically without
+// waiting on the round trip to Reddit.
+fn vote_delta(old: Option<bool>, new: Option<bool>) -> f64 {
+    let as_delta = |liked: Option<bool>| match liked {
+        Some(true) => 1.0,
+        Some(false) => -1.0,
+        None => 0.0,
+    };
+    as_delta(new) - as_delta(old)
+}
+
+// Prefixes a submission's title with its vote/save state so the list and the
+// post header both reflect optimistic updates the same way.
+fn format_submission(submission: &Submission) -> String {
+    let vote_marker = match submission.liked {
+        Some(true) => "▲",
+        Some(false) => "▼",
+        None => " ",
+    };
+    let save_marker = if submission.saved { "★" } else { " " };
+    format!(
+        "{}{} {} ({})",
+        vote_marker, save_marker, submission.title, submission.score
+    )
+}
+
+// Highlights the border of whichever pane currently has focus.
+fn pane_border_style(active: bool) -> Style {
+    if active {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    }
 }
 
 fn ui<B: Backend>(frame: &mut Frame<B>, app: &mut App) {
     match app.view_state {
         ViewState::Subreddit => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+                .split(frame.size());
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(rows[0]);
+
             let list: Vec<ListItem> = app
                 .submissions
                 .items
                 .iter()
-                .map(|i| ListItem::new(i.title.clone()))
+                .map(|i| ListItem::new(format_submission(i)))
                 .collect();
+            let title = if app.loading() {
+                format!("Posts ({}) - loading...", app.sort.label())
+            } else {
+                format!("Posts ({})", app.sort.label())
+            };
             let list = List::new(list)
-                .block(Block::default().borders(Borders::ALL).title("Posts"))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(pane_border_style(app.focus == Focus::List))
+                        .title(title),
+                )
                 .highlight_style(
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
                 )
                 .highlight_symbol("▶ ");
-            let area = frame.size();
-            frame.render_stateful_widget(list, area, &mut app.submissions.state);
+            frame.render_stateful_widget(list, columns[0], &mut app.submissions.state);
+
+            let preview = app
+                .submissions
+                .state
+                .selected()
+                .and_then(|i| app.submissions.items.get(i));
+            let preview_widget = Paragraph::new(match preview {
+                Some(submission) => markdown_to_text(&submission.selftext),
+                None => Text::raw(""),
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(pane_border_style(app.focus == Focus::Preview))
+                    .title(preview.map(format_submission).unwrap_or_default()),
+            )
+            .wrap(Wrap { trim: true })
+            .scroll((app.preview_scroll, 0));
+            frame.render_widget(preview_widget, columns[1]);
+
+            let masked_password: String;
+            let (title, text): (&str, &str) = match &app.input_mode {
+                InputMode::Editing => ("subreddit", app.input.as_str()),
+                InputMode::LoginUsername => ("reddit username", app.input.as_str()),
+                InputMode::LoginPassword => {
+                    masked_password = "*".repeat(app.input.len());
+                    ("reddit password", masked_password.as_str())
+                }
+                InputMode::Normal => match (&app.session, &app.status) {
+                    (_, Some(status)) => ("status", status.as_str()),
+                    (Some(session), None) => (session.username.as_str(), ""),
+                    (None, None) => (
+                        "/ or : subreddit, s sort, L login, u/d vote, S save, Tab focus, \
+                         arrows scroll preview, r retry",
+                        "",
+                    ),
+                },
+            };
+            let input = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(title));
+            frame.render_widget(input, rows[1]);
         }
         ViewState::Post => {
-            // let paragraph = frame.render_widget();
-            let paragraph = tui::widgets::Paragraph::new(
-                app.selection.as_ref().unwrap().selftext.clone(),
-            )
-            .block(Block::default().borders(Borders::ALL).title("Post"));
-            let area = frame.size();
-            frame.render_widget(paragraph, area);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+                .split(frame.size());
+
+            let selection = app.selection.as_ref().unwrap();
+            let paragraph = Paragraph::new(markdown_to_text(&selection.selftext))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format_submission(selection)),
+                )
+                .wrap(Wrap { trim: true })
+                .scroll((app.post_scroll, 0));
+            frame.render_widget(paragraph, chunks[0]);
+
+            let comments: Vec<ListItem> = app
+                .comments
+                .items
+                .iter()
+                .map(|c| {
+                    let prefix = if c.collapsed { "[+] " } else { "" };
+                    ListItem::new(format!(
+                        "{}{}{} ({}) {}",
+                        comment_indent(c.depth),
+                        prefix,
+                        c.author,
+                        c.score,
+                        c.body
+                    ))
+                })
+                .collect();
+            let comments_title = if app.loading() {
+                "Comments - loading..."
+            } else {
+                "Comments"
+            };
+            let comments = List::new(comments)
+                .block(Block::default().borders(Borders::ALL).title(comments_title))
+                .highlight_style(
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("▶ ");
+            frame.render_stateful_widget(comments, chunks[1], &mut app.comments.state);
         }
     }
 }